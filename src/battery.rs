@@ -0,0 +1,100 @@
+//! Battery service (0x180F): Battery Level (0x2A19), read + notify.
+
+use std::{fs, time::Duration};
+
+use bluer::gatt::{
+    local::{
+        characteristic_control, Characteristic, CharacteristicControl,
+        CharacteristicControlHandle, CharacteristicNotify, CharacteristicNotifyMethod,
+        CharacteristicRead, Service, ServiceControlHandle,
+    },
+    CharacteristicWriter,
+};
+
+/// Battery Level 0x2A19.
+const CHAR_BATTERY_LEVEL_UUID: uuid::Uuid =
+    uuid::Uuid::from_u128(0x00002a1900001000800000805f9b34fb);
+
+/// How often the main event loop should refresh and notify the battery level.
+pub const UPDATE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Directory of sysfs power supplies, checked at every read so a battery HAT
+/// hot-plugged after startup is picked up too.
+const SYSFS_POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+/// Reads the current battery percentage from the first sysfs power supply
+/// reporting a `capacity`. Falls back to 100 on mains-only hardware with no
+/// battery.
+pub fn read_percent() -> u8 {
+    let Ok(entries) = fs::read_dir(SYSFS_POWER_SUPPLY_DIR) else {
+        return 100;
+    };
+    for entry in entries.flatten() {
+        let capacity_path = entry.path().join("capacity");
+        if let Ok(contents) = fs::read_to_string(&capacity_path) {
+            if let Ok(percent) = contents.trim().parse::<u8>() {
+                return percent;
+            }
+        }
+    }
+    100
+}
+
+/// Builds the Battery service under `control_handle`. The returned
+/// `CharacteristicControl` yields a `CharacteristicControlEvent::Notify` once
+/// a host subscribes to Battery Level; feed the resulting writer to
+/// [`Monitor::new`] to start pushing periodic updates.
+pub fn service(
+    control_handle: ServiceControlHandle,
+) -> (Service, CharacteristicControl, CharacteristicControlHandle) {
+    let (control, handle) = characteristic_control();
+
+    let service = Service {
+        uuid: crate::SERVICE_BATTERY_UUID,
+        primary: true,
+        characteristics: vec![Characteristic {
+            uuid: CHAR_BATTERY_LEVEL_UUID,
+            read: Some(CharacteristicRead {
+                read: true,
+                fun: Box::new(|_req| Box::pin(async move { Ok(vec![read_percent()]) })),
+                ..Default::default()
+            }),
+            notify: Some(CharacteristicNotify {
+                notify: true,
+                method: CharacteristicNotifyMethod::Io,
+                ..Default::default()
+            }),
+            control_handle: handle.clone(),
+            ..Default::default()
+        }],
+        control_handle,
+        ..Default::default()
+    };
+
+    (service, control, handle)
+}
+
+/// A subscribed Battery Level notify channel.
+pub struct Monitor {
+    writer: CharacteristicWriter,
+}
+
+impl Monitor {
+    /// Wraps a [`CharacteristicWriter`] obtained from a
+    /// `CharacteristicControlEvent::Notify` on the Battery Level channel.
+    pub fn new(writer: CharacteristicWriter) -> Self {
+        Self { writer }
+    }
+
+    /// Notifies the host of the current battery `percent` (0-100).
+    pub async fn notify(&mut self, percent: u8) -> bluer::Result<()> {
+        use tokio::io::AsyncWriteExt;
+        self.writer
+            .write_all(&[percent])
+            .await
+            .map_err(|err| bluer::Error {
+                kind: bluer::ErrorKind::Failed,
+                message: format!("failed to notify battery level: {}", err),
+            })
+    }
+}
@@ -0,0 +1,167 @@
+//! Companion GATT client for `bleyboard`.
+//!
+//! Scans for a running peripheral by its advertised service UUIDs, connects,
+//! discovers its services/characteristics, and drives an end-to-end check:
+//! reads the Device Information strings, subscribes to Battery Level
+//! notifications, and subscribes to the HID Input Report, printing decoded
+//! keystrokes as they arrive. Lets contributors validate the server without
+//! a real OS HID host in the loop.
+
+use std::time::Duration;
+
+use bluer::{gatt::remote::Characteristic, Adapter, AdapterEvent, Device, Session};
+use futures::{pin_mut, StreamExt};
+use tokio::time::sleep;
+
+const SERVICE_BATTERY_UUID: uuid::Uuid = uuid::Uuid::from_u128(0x0000180f00001000800000805f9b34fb);
+const SERVICE_DEVICE_INFO_UUID: uuid::Uuid =
+    uuid::Uuid::from_u128(0x0000180a00001000800000805f9b34fb);
+const SERVICE_HID_UUID: uuid::Uuid = uuid::Uuid::from_u128(0x0000181200001000800000805f9b34fb);
+
+const CHAR_MODEL_NUMBER_UUID: uuid::Uuid =
+    uuid::Uuid::from_u128(0x00002a2400001000800000805f9b34fb);
+const CHAR_SERIAL_NUMBER_UUID: uuid::Uuid =
+    uuid::Uuid::from_u128(0x00002a2500001000800000805f9b34fb);
+const CHAR_MANUFACTURER_NAME_UUID: uuid::Uuid =
+    uuid::Uuid::from_u128(0x00002a2900001000800000805f9b34fb);
+const CHAR_BATTERY_LEVEL_UUID: uuid::Uuid =
+    uuid::Uuid::from_u128(0x00002a1900001000800000805f9b34fb);
+const CHAR_REPORT_UUID: uuid::Uuid = uuid::Uuid::from_u128(0x00002a4d00001000800000805f9b34fb);
+
+/// How many connection attempts to make in total before giving up.
+const CONNECT_RETRIES: u32 = 3;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> bluer::Result<()> {
+    println!("Scanning for a bleyboard peripheral…");
+    let session = Session::new().await?;
+    let adapter = session.default_adapter().await?;
+    adapter.set_powered(true).await?;
+
+    let device = discover(&adapter).await?;
+    println!(
+        "Found {} ({})",
+        device.address(),
+        device.name().await?.unwrap_or_default()
+    );
+
+    connect(&device).await?;
+    println!("Connected, discovering services…");
+
+    let model = read_string(&device, SERVICE_DEVICE_INFO_UUID, CHAR_MODEL_NUMBER_UUID).await?;
+    let serial = read_string(&device, SERVICE_DEVICE_INFO_UUID, CHAR_SERIAL_NUMBER_UUID).await?;
+    let manufacturer = read_string(
+        &device,
+        SERVICE_DEVICE_INFO_UUID,
+        CHAR_MANUFACTURER_NAME_UUID,
+    )
+    .await?;
+    println!("Model Number: {}", model);
+    println!("Serial Number: {}", serial);
+    println!("Manufacturer: {}", manufacturer);
+
+    let battery = find_characteristic(&device, SERVICE_BATTERY_UUID, CHAR_BATTERY_LEVEL_UUID).await?;
+    let battery_notify = battery.notify().await?;
+    pin_mut!(battery_notify);
+    println!("Subscribed to Battery Level notifications");
+
+    let report = find_characteristic(&device, SERVICE_HID_UUID, CHAR_REPORT_UUID).await?;
+    let report_notify = report.notify().await?;
+    pin_mut!(report_notify);
+    println!("Subscribed to HID Input Report notifications");
+
+    println!("Listening for notifications. Press Ctrl-C to stop.");
+    loop {
+        tokio::select! {
+            Some(value) = battery_notify.next() => {
+                println!("Battery level: {}%", value.first().copied().unwrap_or(0));
+            }
+            Some(value) = report_notify.next() => {
+                print_report(&value);
+            }
+            else => break,
+        }
+    }
+
+    Ok(())
+}
+
+/// Scans until a device advertising the HID service is found.
+async fn discover(adapter: &Adapter) -> bluer::Result<Device> {
+    let discover = adapter.discover_devices().await?;
+    pin_mut!(discover);
+    while let Some(evt) = discover.next().await {
+        if let AdapterEvent::DeviceAdded(address) = evt {
+            let device = adapter.device(address)?;
+            if device
+                .uuids()
+                .await?
+                .unwrap_or_default()
+                .contains(&SERVICE_HID_UUID)
+            {
+                return Ok(device);
+            }
+        }
+    }
+    Err(bluer::Error {
+        kind: bluer::ErrorKind::NotFound,
+        message: "no bleyboard peripheral found".to_string(),
+    })
+}
+
+/// Connects to `device`, retrying a couple of times since a peripheral
+/// that just finished advertising can be slow to accept the first attempt.
+async fn connect(device: &Device) -> bluer::Result<()> {
+    for attempt in 1..=CONNECT_RETRIES {
+        match device.connect().await {
+            Ok(()) => return Ok(()),
+            Err(err) if attempt < CONNECT_RETRIES => {
+                println!("Connect attempt {} failed: {}; retrying", attempt, err);
+                sleep(Duration::from_secs(1)).await;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    unreachable!("loop always returns or propagates on the last attempt")
+}
+
+async fn find_characteristic(
+    device: &Device,
+    service_uuid: uuid::Uuid,
+    characteristic_uuid: uuid::Uuid,
+) -> bluer::Result<Characteristic> {
+    for service in device.services().await? {
+        if service.uuid().await? == service_uuid {
+            for characteristic in service.characteristics().await? {
+                if characteristic.uuid().await? == characteristic_uuid {
+                    return Ok(characteristic);
+                }
+            }
+        }
+    }
+    Err(bluer::Error {
+        kind: bluer::ErrorKind::NotFound,
+        message: format!("characteristic {} not found", characteristic_uuid),
+    })
+}
+
+async fn read_string(
+    device: &Device,
+    service_uuid: uuid::Uuid,
+    characteristic_uuid: uuid::Uuid,
+) -> bluer::Result<String> {
+    let characteristic = find_characteristic(device, service_uuid, characteristic_uuid).await?;
+    let value = characteristic.read().await?;
+    Ok(String::from_utf8_lossy(&value).into_owned())
+}
+
+/// Decodes a boot-keyboard input report `[modifiers, reserved, k1..k6]`.
+fn print_report(value: &[u8]) {
+    if value.len() < 8 {
+        println!("Malformed HID report: {:x?}", value);
+        return;
+    }
+    let modifiers = value[0];
+    let keycodes: Vec<u8> = value[2..8].iter().copied().filter(|&k| k != 0).collect();
+    println!("Input Report: modifiers=0x{:02x} keycodes={:x?}", modifiers, keycodes);
+}
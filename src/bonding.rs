@@ -0,0 +1,123 @@
+//! Persists which central has bonded with this peripheral.
+//!
+//! BlueZ's `LEAdvertisement1` interface has no knob for directed
+//! advertising, but it does auto-reconnect to devices that are both paired
+//! and trusted. So instead of readvertising to the world on every run, once
+//! we know a host has bonded we mark it trusted and stop advertising
+//! generally discoverable, letting BlueZ re-establish the link in the
+//! background.
+//!
+//! Pairing happens *after* a device is first seen, and `adapter.events()`
+//! only surfaces adapter-level changes, not a given device's own property
+//! changes — so [`watch_for_pairing`] is spawned per `DeviceAdded` to watch
+//! that one device's event stream and report back once `Paired` turns true.
+
+use std::{fs, io, path::Path};
+
+use bluer::{Adapter, Address, DeviceEvent, DeviceProperty};
+use futures::{pin_mut, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Where bonding state is stored across runs.
+const BONDS_PATH: &str = "/var/lib/bleyboard/bonds.json";
+
+/// The bonded host remembered from a previous run, if any.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Bonds {
+    host: Option<String>,
+}
+
+impl Bonds {
+    /// Loads bonding state from [`BONDS_PATH`], defaulting to "no known
+    /// host" if the file is absent or unreadable.
+    pub fn load() -> Self {
+        match fs::read_to_string(BONDS_PATH) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_default(),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Persists the current bonding state to [`BONDS_PATH`].
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(parent) = Path::new(BONDS_PATH).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        fs::write(BONDS_PATH, contents)
+    }
+
+    /// Clears the stored bond. Backs the `--forget` flag.
+    pub fn forget(&mut self) {
+        self.host = None;
+    }
+
+    /// The address of the bonded host, if one is known and well-formed.
+    pub fn host(&self) -> Option<Address> {
+        self.host.as_deref().and_then(|addr| addr.parse().ok())
+    }
+
+    /// Records `address` as the bonded host.
+    fn set_host(&mut self, address: Address) {
+        self.host = Some(address.to_string());
+    }
+}
+
+/// Watches `address` for the `Paired` property turning true, then reports it
+/// on `bonded_tx` for the main loop to hand to [`remember`].
+///
+/// Meant to be spawned (via `tokio::spawn`) as soon as `address` shows up in
+/// `AdapterEvent::DeviceAdded`: a central is usually only added moments
+/// before it pairs, so checking `is_paired` once at that instant almost
+/// always misses the transition. Returns once `Paired` is observed true, or
+/// once the device's event stream ends (e.g. it disconnected without ever
+/// pairing).
+pub async fn watch_for_pairing(
+    adapter: &Adapter,
+    address: Address,
+    bonded_tx: UnboundedSender<Address>,
+) -> bluer::Result<()> {
+    let device = adapter.device(address)?;
+    if device.is_paired().await? {
+        let _ = bonded_tx.send(address);
+        return Ok(());
+    }
+
+    let events = device.events().await?;
+    pin_mut!(events);
+    while let Some(evt) = events.next().await {
+        if let DeviceEvent::PropertyChanged(DeviceProperty::Paired(true)) = evt {
+            let _ = bonded_tx.send(address);
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Called once `address` has been observed pairing (see
+/// [`watch_for_pairing`]): marks it trusted so BlueZ reconnects to it
+/// automatically in the future, and records it as the bonded host.
+///
+/// Only ever overwrites an existing bond with a *different* address when no
+/// host is currently remembered — once a host is bonded, a stray pairing
+/// from some other central (e.g. left in discoverable mode longer than
+/// intended) must not silently hijack the stored bond. Use `--forget` to
+/// clear it deliberately first.
+pub async fn remember(adapter: &Adapter, address: Address, bonds: &mut Bonds) -> bluer::Result<()> {
+    if let Some(existing) = bonds.host() {
+        if existing != address {
+            return Ok(());
+        }
+    }
+
+    let device = adapter.device(address)?;
+    if device.is_paired().await? {
+        device.set_trusted(true).await?;
+        bonds.set_host(address);
+        if let Err(err) = bonds.save() {
+            log::error!("Failed to persist bonding state for {}: {}", address, err);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,175 @@
+//! Fluent builder for assembling the peripheral's GATT application.
+//!
+//! Hand-constructing every `Service`/`Characteristic` inline in `main` gets
+//! verbose and error-prone as the HID/battery/DIS profiles grow. This module
+//! lets callers declare which services a profile exposes and compiles that
+//! declaration down to the `bluer` `Application`, the
+//! `service_control`/`characteristic_control` handles the event loop needs
+//! to react to characteristic events, and the advertisement metadata
+//! (service UUIDs, appearance) that matches what was actually built.
+
+use bluer::gatt::local::{
+    service_control, Application, CharacteristicControl, CharacteristicControlHandle, Service,
+    ServiceControl,
+};
+
+use crate::{battery, device_info, hid};
+
+/// Everything a caller needs after [`DeviceBuilder::build`]: the `bluer`
+/// application to serve, the handles to drive from the event loop, and the
+/// advertisement metadata matching the declared services.
+pub struct Device {
+    pub application: Application,
+    pub service_control: Option<ServiceControl>,
+    pub battery: Option<(CharacteristicControl, CharacteristicControlHandle)>,
+    pub hid_keyboard: Option<(CharacteristicControl, CharacteristicControlHandle, hid::ReportState)>,
+    pub service_uuids: Vec<uuid::Uuid>,
+    pub appearance: Option<u16>,
+}
+
+/// Declares which GATT services a peripheral profile exposes, then compiles
+/// them into a [`Device`]. Composing different profiles (keyboard-only vs.
+/// keyboard+gamepad) is just a matter of calling a different set of these
+/// methods before [`build`](DeviceBuilder::build).
+#[derive(Default)]
+pub struct DeviceBuilder {
+    battery: bool,
+    device_info: Option<device_info::DeviceInfo>,
+    scan_parameters: bool,
+    hid_keyboard: bool,
+}
+
+impl DeviceBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the Battery service (0x180F), notifying subscribers with the
+    /// level read by [`battery::read_percent`].
+    pub fn battery_service(mut self) -> Self {
+        self.battery = true;
+        self
+    }
+
+    /// Adds the Device Information service (0x180A). Chain calls on the
+    /// returned [`DeviceInfoBuilder`] to override the default strings, then
+    /// call [`DeviceInfoBuilder::done`] to return to this builder.
+    pub fn device_info(self) -> DeviceInfoBuilder {
+        DeviceInfoBuilder {
+            device: self,
+            info: device_info::DeviceInfo::default(),
+        }
+    }
+
+    /// Adds the (currently empty) Scan Parameters service (0x1813).
+    pub fn scan_parameters(mut self) -> Self {
+        self.scan_parameters = true;
+        self
+    }
+
+    /// Adds the HID service (0x1812) with a single notifying Input Report,
+    /// ready to drive with [`hid::Keyboard`].
+    pub fn hid_keyboard(mut self) -> Self {
+        self.hid_keyboard = true;
+        self
+    }
+
+    /// Compiles the declared services into a `bluer` [`Application`] plus
+    /// the handles and advertisement metadata the caller needs.
+    pub fn build(self) -> Device {
+        let mut services = Vec::new();
+        let mut service_uuids = Vec::new();
+        let mut appearance = None;
+        let mut service_control = None;
+        let mut battery = None;
+        let mut hid_keyboard = None;
+
+        if self.battery {
+            let (control, control_handle) = service_control();
+            let (service, characteristic_control, characteristic_handle) =
+                battery::service(control_handle);
+            services.push(service);
+            service_uuids.push(crate::SERVICE_BATTERY_UUID);
+            battery = Some((characteristic_control, characteristic_handle));
+            service_control = Some(control);
+        }
+
+        if let Some(info) = self.device_info {
+            services.push(device_info::service(&info));
+        }
+
+        if self.scan_parameters {
+            services.push(Service {
+                uuid: crate::SERVICE_SCAN_PARAMS_UUID,
+                primary: false,
+                ..Default::default()
+            });
+        }
+
+        if self.hid_keyboard {
+            let (service, control, handle, report_state) = hid::service();
+            services.push(service);
+            service_uuids.push(crate::SERVICE_HID_UUID);
+            appearance = Some(crate::APPEARANCE_HID_GAMEPAD);
+            hid_keyboard = Some((control, handle, report_state));
+        }
+
+        Device {
+            application: Application {
+                services,
+                ..Default::default()
+            },
+            service_control,
+            battery,
+            hid_keyboard,
+            service_uuids,
+            appearance,
+        }
+    }
+}
+
+/// Sub-builder for overriding the Device Information defaults. Returned by
+/// [`DeviceBuilder::device_info`].
+pub struct DeviceInfoBuilder {
+    device: DeviceBuilder,
+    info: device_info::DeviceInfo,
+}
+
+impl DeviceInfoBuilder {
+    pub fn model(mut self, value: impl Into<String>) -> Self {
+        self.info.model_number = value.into();
+        self
+    }
+
+    pub fn serial(mut self, value: impl Into<String>) -> Self {
+        self.info.serial_number = value.into();
+        self
+    }
+
+    pub fn firmware(mut self, value: impl Into<String>) -> Self {
+        self.info.firmware_revision = value.into();
+        self
+    }
+
+    pub fn hardware(mut self, value: impl Into<String>) -> Self {
+        self.info.hardware_revision = value.into();
+        self
+    }
+
+    pub fn software(mut self, value: impl Into<String>) -> Self {
+        self.info.software_revision = value.into();
+        self
+    }
+
+    pub fn manufacturer(mut self, value: impl Into<String>) -> Self {
+        self.info.manufacturer_name = value.into();
+        self
+    }
+
+    /// Returns to the parent [`DeviceBuilder`] with the overridden Device
+    /// Information strings recorded.
+    pub fn done(mut self) -> DeviceBuilder {
+        self.device.device_info = Some(self.info);
+        self.device
+    }
+}
@@ -0,0 +1,57 @@
+//! Command-line interface for the `bleyboard` peripheral binary.
+
+use std::time::Duration;
+
+use clap::Parser;
+
+/// Maximum BLE advertisement timeout (Bluetooth Core Specification §5.1.1).
+const MAX_ADV_TIMEOUT_SECS: u64 = 180;
+
+/// A Bluetooth LE HID keyboard peripheral.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+pub struct Cli {
+    /// Local name advertised to centrals.
+    #[arg(long, default_value = "Luc's bleyboard")]
+    pub name: String,
+
+    /// How long to advertise for, in seconds.
+    #[arg(long, default_value_t = 120, value_parser = parse_timeout_secs)]
+    pub timeout: u64,
+
+    /// Adapter to advertise on, e.g. "hci0". Defaults to the system's
+    /// default adapter.
+    #[arg(long)]
+    pub adapter: Option<String>,
+
+    /// Manufacturer id embedded in the advertisement's manufacturer data.
+    #[arg(long, default_value_t = 0xf00d)]
+    pub manufacturer_id: u16,
+
+    /// Clear any previously bonded host and fall back to general
+    /// discoverable advertising.
+    #[arg(long)]
+    pub forget: bool,
+}
+
+/// Rejects timeouts above the BLE advertising timeout limit, so the error
+/// surfaces at argument-parsing time instead of as an opaque `bluer` error.
+fn parse_timeout_secs(value: &str) -> Result<u64, String> {
+    let secs: u64 = value
+        .parse()
+        .map_err(|_| format!("`{}` is not a valid number of seconds", value))?;
+    if secs > MAX_ADV_TIMEOUT_SECS {
+        return Err(format!(
+            "timeout must be at most {} seconds (BLE advertising timeout limit)",
+            MAX_ADV_TIMEOUT_SECS
+        ));
+    }
+    Ok(secs)
+}
+
+impl Cli {
+    /// The requested advertisement timeout as a [`Duration`].
+    pub fn adv_timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout)
+    }
+}
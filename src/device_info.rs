@@ -0,0 +1,79 @@
+//! Device Information service (0x180A): static identification strings.
+
+use bluer::gatt::local::{Characteristic, CharacteristicRead, Service};
+
+/// Model Number String 0x2A24.
+const CHAR_MODEL_NUMBER_UUID: uuid::Uuid =
+    uuid::Uuid::from_u128(0x00002a2400001000800000805f9b34fb);
+/// Serial Number String 0x2A25.
+const CHAR_SERIAL_NUMBER_UUID: uuid::Uuid =
+    uuid::Uuid::from_u128(0x00002a2500001000800000805f9b34fb);
+/// Firmware Revision String 0x2A26.
+const CHAR_FIRMWARE_REVISION_UUID: uuid::Uuid =
+    uuid::Uuid::from_u128(0x00002a2600001000800000805f9b34fb);
+/// Hardware Revision String 0x2A27.
+const CHAR_HARDWARE_REVISION_UUID: uuid::Uuid =
+    uuid::Uuid::from_u128(0x00002a2700001000800000805f9b34fb);
+/// Software Revision String 0x2A28.
+const CHAR_SOFTWARE_REVISION_UUID: uuid::Uuid =
+    uuid::Uuid::from_u128(0x00002a2800001000800000805f9b34fb);
+/// Manufacturer Name String 0x2A29.
+const CHAR_MANUFACTURER_NAME_UUID: uuid::Uuid =
+    uuid::Uuid::from_u128(0x00002a2900001000800000805f9b34fb);
+
+/// Constant identification strings exposed through the Device Information
+/// service.
+pub struct DeviceInfo {
+    pub model_number: String,
+    pub serial_number: String,
+    pub firmware_revision: String,
+    pub hardware_revision: String,
+    pub software_revision: String,
+    pub manufacturer_name: String,
+}
+
+impl Default for DeviceInfo {
+    fn default() -> Self {
+        Self {
+            model_number: "bleyboard-1".into(),
+            serial_number: "0001".into(),
+            firmware_revision: "0.1.0".into(),
+            hardware_revision: "rev1".into(),
+            software_revision: "0.1.0".into(),
+            manufacturer_name: "Luc Dachary".into(),
+        }
+    }
+}
+
+/// Builds a read-only characteristic that always returns `value`.
+fn constant(uuid: uuid::Uuid, value: String) -> Characteristic {
+    Characteristic {
+        uuid,
+        read: Some(CharacteristicRead {
+            read: true,
+            fun: Box::new(move |_req| {
+                let value = value.clone();
+                Box::pin(async move { Ok(value.into_bytes()) })
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Builds the Device Information service backed by `info`.
+pub fn service(info: &DeviceInfo) -> Service {
+    Service {
+        uuid: crate::SERVICE_DEVICE_INFO_UUID,
+        primary: true,
+        characteristics: vec![
+            constant(CHAR_MODEL_NUMBER_UUID, info.model_number.clone()),
+            constant(CHAR_SERIAL_NUMBER_UUID, info.serial_number.clone()),
+            constant(CHAR_FIRMWARE_REVISION_UUID, info.firmware_revision.clone()),
+            constant(CHAR_HARDWARE_REVISION_UUID, info.hardware_revision.clone()),
+            constant(CHAR_SOFTWARE_REVISION_UUID, info.software_revision.clone()),
+            constant(CHAR_MANUFACTURER_NAME_UUID, info.manufacturer_name.clone()),
+        ],
+        ..Default::default()
+    }
+}
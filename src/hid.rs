@@ -0,0 +1,275 @@
+//! HID-over-GATT keyboard profile (HID service, 0x1812).
+//!
+//! Implements enough of the Bluetooth HID-over-GATT profile to expose a
+//! boot-compatible keyboard: HID Information, Report Map, HID Control Point,
+//! Protocol Mode, and a single notifying Input Report.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use bluer::gatt::{
+    local::{
+        characteristic_control, Characteristic, CharacteristicControl,
+        CharacteristicControlHandle, CharacteristicNotify, CharacteristicNotifyMethod,
+        CharacteristicRead, CharacteristicWrite, CharacteristicWriteMethod, Descriptor,
+        DescriptorRead, Service,
+    },
+    CharacteristicWriter,
+};
+use tokio::time::sleep;
+
+/// HID Information 0x2A4A.
+const CHAR_HID_INFORMATION_UUID: uuid::Uuid =
+    uuid::Uuid::from_u128(0x00002a4a00001000800000805f9b34fb);
+/// Report Map 0x2A4B.
+const CHAR_REPORT_MAP_UUID: uuid::Uuid = uuid::Uuid::from_u128(0x00002a4b00001000800000805f9b34fb);
+/// HID Control Point 0x2A4C.
+const CHAR_HID_CONTROL_POINT_UUID: uuid::Uuid =
+    uuid::Uuid::from_u128(0x00002a4c00001000800000805f9b34fb);
+/// Report 0x2A4D.
+const CHAR_REPORT_UUID: uuid::Uuid = uuid::Uuid::from_u128(0x00002a4d00001000800000805f9b34fb);
+/// Protocol Mode 0x2A4E.
+const CHAR_PROTOCOL_MODE_UUID: uuid::Uuid =
+    uuid::Uuid::from_u128(0x00002a4e00001000800000805f9b34fb);
+/// Report Reference descriptor 0x2908.
+const DESC_REPORT_REFERENCE_UUID: uuid::Uuid =
+    uuid::Uuid::from_u128(0x0000290800001000800000805f9b34fb);
+
+/// Report id used for the single Input Report we expose.
+const INPUT_REPORT_ID: u8 = 0x01;
+/// `report_reference_type` value meaning "Input Report" (§3.10, Assigned Numbers).
+const REPORT_TYPE_INPUT: u8 = 0x01;
+
+/// Boot-compatible keyboard report descriptor: 8 modifier bits, 1 reserved
+/// byte, 1 LED output byte, 6 keycode bytes. Mirrors the classic USB HID boot
+/// keyboard report used throughout §E.6 of the HID Usage Tables.
+#[rustfmt::skip]
+const REPORT_MAP: &[u8] = &[
+    0x05, 0x01, // Usage Page (Generic Desktop)
+    0x09, 0x06, // Usage (Keyboard)
+    0xA1, 0x01, // Collection (Application)
+    0x85, INPUT_REPORT_ID, //   Report Id (1)
+    0x05, 0x07, //   Usage Page (Key Codes)
+    0x19, 0xE0, //   Usage Minimum (224)
+    0x29, 0xE7, //   Usage Maximum (231)
+    0x15, 0x00, //   Logical Minimum (0)
+    0x25, 0x01, //   Logical Maximum (1)
+    0x75, 0x01, //   Report Size (1)
+    0x95, 0x08, //   Report Count (8)
+    0x81, 0x02, //   Input (Data, Variable, Absolute) ; modifier byte
+    0x95, 0x01, //   Report Count (1)
+    0x75, 0x08, //   Report Size (8)
+    0x81, 0x01, //   Input (Constant) ; reserved byte
+    0x95, 0x05, //   Report Count (5)
+    0x75, 0x01, //   Report Size (1)
+    0x05, 0x08, //   Usage Page (LEDs)
+    0x19, 0x01, //   Usage Minimum (1)
+    0x29, 0x05, //   Usage Maximum (5)
+    0x91, 0x02, //   Output (Data, Variable, Absolute) ; LED report
+    0x95, 0x01, //   Report Count (1)
+    0x75, 0x03, //   Report Size (3)
+    0x91, 0x01, //   Output (Constant) ; LED padding
+    0x95, 0x06, //   Report Count (6)
+    0x75, 0x08, //   Report Size (8)
+    0x15, 0x00, //   Logical Minimum (0)
+    0x26, 0xFF, 0x00, //   Logical Maximum (255, 2-byte form: 0xFF alone would sign-extend to -1)
+    0x05, 0x07, //   Usage Page (Key Codes)
+    0x19, 0x00, //   Usage Minimum (0)
+    0x29, 0xFF, //   Usage Maximum (255)
+    0x81, 0x00, //   Input (Data, Array) ; keycode array
+    0xC0,       // End Collection
+];
+
+/// The last Input Report sent, shared between the notify side
+/// ([`Keyboard`]) and the characteristic's `read` callback so a host that
+/// reads the Report before (or between) keystrokes gets the current value
+/// instead of nothing, per the HOGP requirement that Report be readable.
+pub type ReportState = Arc<Mutex<[u8; 8]>>;
+
+/// Builds the HID service along with the `CharacteristicControl` stream that
+/// surfaces Input Report notify subscriptions. Once the central subscribes,
+/// feed the resulting [`CharacteristicWriter`] and the returned
+/// [`ReportState`] to [`Keyboard::new`].
+pub fn service() -> (Service, CharacteristicControl, CharacteristicControlHandle, ReportState) {
+    let (input_report_control, input_report_handle) = characteristic_control();
+    let report_state: ReportState = Arc::new(Mutex::new([0u8; 8]));
+    let read_state = report_state.clone();
+
+    let service = Service {
+        uuid: crate::SERVICE_HID_UUID,
+        primary: true,
+        characteristics: vec![
+            Characteristic {
+                // HID Information: bcdHID=0x0111, country code 0x00 (not localized), flags 0x02
+                // (NormallyConnectable).
+                uuid: CHAR_HID_INFORMATION_UUID,
+                read: Some(CharacteristicRead {
+                    read: true,
+                    fun: Box::new(|_req| {
+                        Box::pin(async move { Ok(vec![0x11, 0x01, 0x00, 0x02]) })
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            Characteristic {
+                uuid: CHAR_REPORT_MAP_UUID,
+                read: Some(CharacteristicRead {
+                    read: true,
+                    fun: Box::new(|_req| Box::pin(async move { Ok(REPORT_MAP.to_vec()) })),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            Characteristic {
+                uuid: CHAR_HID_CONTROL_POINT_UUID,
+                write: Some(CharacteristicWrite {
+                    write_without_response: true,
+                    method: CharacteristicWriteMethod::Fun(Box::new(|value, _req| {
+                        Box::pin(async move {
+                            log::debug!("HID Control Point written: {:x?}", value);
+                            Ok(())
+                        })
+                    })),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            Characteristic {
+                uuid: CHAR_PROTOCOL_MODE_UUID,
+                read: Some(CharacteristicRead {
+                    read: true,
+                    // Report Protocol Mode (0x01), the only mode this profile supports.
+                    fun: Box::new(|_req| Box::pin(async move { Ok(vec![0x01]) })),
+                    ..Default::default()
+                }),
+                write: Some(CharacteristicWrite {
+                    write_without_response: true,
+                    method: CharacteristicWriteMethod::Fun(Box::new(|_value, _req| {
+                        Box::pin(async move { Ok(()) })
+                    })),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            Characteristic {
+                uuid: CHAR_REPORT_UUID,
+                // HOGP requires Report (Input) to be readable, not just
+                // notifiable, so a host can fetch the current state on
+                // connect instead of waiting for the next keystroke.
+                read: Some(CharacteristicRead {
+                    read: true,
+                    fun: Box::new(move |_req| {
+                        let state = read_state.clone();
+                        Box::pin(async move { Ok(state.lock().unwrap().to_vec()) })
+                    }),
+                    ..Default::default()
+                }),
+                notify: Some(CharacteristicNotify {
+                    notify: true,
+                    method: CharacteristicNotifyMethod::Io,
+                    ..Default::default()
+                }),
+                descriptors: vec![Descriptor {
+                    uuid: DESC_REPORT_REFERENCE_UUID,
+                    read: Some(DescriptorRead {
+                        read: true,
+                        fun: Box::new(|_req| {
+                            Box::pin(async move { Ok(vec![INPUT_REPORT_ID, REPORT_TYPE_INPUT]) })
+                        }),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }],
+                control_handle: input_report_handle.clone(),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+
+    (service, input_report_control, input_report_handle, report_state)
+}
+
+/// Delay between a keypress report and its release, and between successive
+/// characters in [`Keyboard::type_text`], so a host sees discrete keystrokes
+/// rather than a single coalesced report.
+const KEY_HOLD: Duration = Duration::from_millis(20);
+
+/// A subscribed Input Report channel, ready to send 8-byte boot keyboard
+/// reports once a host has enabled notifications.
+pub struct Keyboard {
+    writer: CharacteristicWriter,
+    state: ReportState,
+}
+
+impl Keyboard {
+    /// Wraps a [`CharacteristicWriter`] obtained from a
+    /// `CharacteristicControlEvent::Notify` on the Input Report channel,
+    /// alongside the [`ReportState`] returned by [`service`] so reads see
+    /// what was last sent.
+    pub fn new(writer: CharacteristicWriter, state: ReportState) -> Self {
+        Self { writer, state }
+    }
+
+    /// Sends a single keypress report: `modifiers` is the bitmask of
+    /// left/right Ctrl/Shift/Alt/Meta (usages 0xE0-0xE7), and `keycode` is a
+    /// USB HID key usage (e.g. 0x04 for 'a'). Only one simultaneous keycode is
+    /// supported; the remaining five keycode slots are left empty.
+    pub async fn send_key(&mut self, keycode: u8, modifiers: u8) -> bluer::Result<()> {
+        let report = [modifiers, 0x00, keycode, 0x00, 0x00, 0x00, 0x00, 0x00];
+        self.write_report(&report).await
+    }
+
+    /// Sends an all-zero report, releasing any keys held down by a prior
+    /// [`Keyboard::send_key`].
+    pub async fn release_all(&mut self) -> bluer::Result<()> {
+        self.write_report(&[0u8; 8]).await
+    }
+
+    /// Types `text` one character at a time via [`char_to_report`], holding
+    /// and releasing each key in turn. Characters with no HID mapping are
+    /// skipped.
+    pub async fn type_text(&mut self, text: &str) -> bluer::Result<()> {
+        for c in text.chars() {
+            let Some((keycode, modifiers)) = char_to_report(c) else {
+                continue;
+            };
+            self.send_key(keycode, modifiers).await?;
+            sleep(KEY_HOLD).await;
+            self.release_all().await?;
+            sleep(KEY_HOLD).await;
+        }
+        Ok(())
+    }
+
+    async fn write_report(&mut self, report: &[u8; 8]) -> bluer::Result<()> {
+        use tokio::io::AsyncWriteExt;
+        *self.state.lock().unwrap() = *report;
+        self.writer
+            .write_all(report)
+            .await
+            .map_err(|err| bluer::Error {
+                kind: bluer::ErrorKind::Failed,
+                message: format!("failed to write HID report: {}", err),
+            })
+    }
+}
+
+/// Maps an ASCII character to a `(keycode, modifiers)` USB HID Keyboard/Keypad
+/// usage pair. Covers letters, digits and space — enough to type plain text;
+/// anything else returns `None`.
+pub fn char_to_report(c: char) -> Option<(u8, u8)> {
+    /// Left Shift (usage 0xE1), bit 1 of the modifier byte.
+    const LEFT_SHIFT: u8 = 0x02;
+    match c {
+        'a'..='z' => Some((0x04 + (c as u8 - b'a'), 0x00)),
+        'A'..='Z' => Some((0x04 + (c.to_ascii_lowercase() as u8 - b'a'), LEFT_SHIFT)),
+        '1'..='9' => Some((0x1e + (c as u8 - b'1'), 0x00)),
+        '0' => Some((0x27, 0x00)),
+        ' ' => Some((0x2c, 0x00)),
+        _ => None,
+    }
+}
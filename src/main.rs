@@ -1,17 +1,7 @@
 use ansi_term::Colour::Green;
 use ansi_term::Style;
-use bluer::{
-    adv::Advertisement,
-    adv::Type,
-    gatt::local::{
-        characteristic_control, service_control, Application, Characteristic,
-        CharacteristicControlEvent, CharacteristicNotify, CharacteristicNotifyMethod,
-        CharacteristicRead, CharacteristicWrite, CharacteristicWriteMethod, Service,
-    },
-    gatt::{CharacteristicReader, CharacteristicWriter},
-    ErrorKind,
-};
-use futures::{future, pin_mut, StreamExt};
+use bluer::{adv::Advertisement, adv::Type, gatt::local::CharacteristicControlEvent, ErrorKind};
+use futures::{pin_mut, StreamExt};
 use indicatif::ProgressBar;
 use log::error;
 use log::LevelFilter;
@@ -19,10 +9,21 @@ use log::{debug, info};
 use std::{collections::BTreeMap, time::Duration};
 use syslog::{BasicLogger, Facility, Formatter3164};
 use tokio::{
-    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
-    time::sleep,
+    io::{AsyncBufReadExt, BufReader},
+    sync::mpsc,
+    time::{interval, sleep},
 };
 
+mod battery;
+mod bonding;
+mod builder;
+mod cli;
+mod device_info;
+mod hid;
+
+use builder::DeviceBuilder;
+use clap::Parser;
+
 // Standard 128 bits UUID: 0000XXXX-0000-1000-8000-00805f9b34fb
 
 const SERVICE_BATTERY_UUID: uuid::Uuid = uuid::Uuid::from_u128(0x0000180f00001000800000805f9b34fb);
@@ -32,13 +33,6 @@ const SERVICE_SCAN_PARAMS_UUID: uuid::Uuid =
     uuid::Uuid::from_u128(0x0000181300001000800000805f9b34fb);
 const SERVICE_HID_UUID: uuid::Uuid = uuid::Uuid::from_u128(0x0000181200001000800000805f9b34fb);
 
-/// Characteristic UUID for GATT example.
-const CHARACTERISTIC_UUID: uuid::Uuid = uuid::Uuid::from_u128(0xF00DC0DE00001);
-
-/// Manufacturer id for LE advertisement.
-// TODO replace with custom
-#[allow(dead_code)]
-const MANUFACTURER_ID: u16 = 0xf00d;
 /// Keyboard appearance.
 //const APPEARANCE_HID_KEYBOARD: u16 = 0x03c1;
 //const APPEARANCE_HID_MOUSE: u16 = 962;
@@ -47,8 +41,19 @@ const APPEARANCE_HID_GAMEPAD: u16 = 964;
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> bluer::Result<()> {
+    let cli = cli::Cli::parse();
+
     println!("Starting Bleyboard…");
 
+    let mut bonds = bonding::Bonds::load();
+    if cli.forget {
+        bonds.forget();
+        if let Err(err) = bonds.save() {
+            eprintln!("Failed to clear stored bonds: {}", err);
+        }
+        println!("Forgot the previously bonded host.");
+    }
+
     print!("Configuring syslog… ");
     let formatter = Formatter3164 {
         facility: Facility::LOG_USER,
@@ -66,9 +71,38 @@ async fn main() -> bluer::Result<()> {
 
     let session = bluer::Session::new().await?;
 
-    let adapter = session.default_adapter().await?;
+    let adapter = match &cli.adapter {
+        Some(name) => session.adapter(name)?,
+        None => session.default_adapter().await?,
+    };
     adapter.set_powered(true).await?;
 
+    print!("Configuring HID Keyboard services\u{2026} ");
+    let device = DeviceBuilder::new()
+        .battery_service()
+        .device_info()
+        .done()
+        .scan_parameters()
+        .hid_keyboard()
+        .build();
+    let app_handle = adapter.serve_gatt_application(device.application).await?;
+    println!("{}", Green.bold().paint("OK"));
+
+    if let Some(service_control) = &device.service_control {
+        info!("Service handle is 0x{:x}", service_control.handle()?);
+    }
+    let (battery_control, _battery_handle) = device.battery.expect("battery service declared");
+    let (hid_report_control, _hid_report_handle, hid_report_state) =
+        device.hid_keyboard.expect("HID keyboard service declared");
+    info!(
+        "Battery Level characteristic handle is 0x{:x}",
+        battery_control.handle()?
+    );
+    info!(
+        "HID Input Report characteristic handle is 0x{:x}",
+        hid_report_control.handle()?
+    );
+
     print!("Configuring advertisement… ");
     info!(
         "Advertising on Bluetooth adapter {} with address {}",
@@ -77,22 +111,32 @@ async fn main() -> bluer::Result<()> {
     );
     let mut manufacturer_data = BTreeMap::new();
     // DEV
-    manufacturer_data.insert(MANUFACTURER_ID, vec![0x21, 0x22, 0x23, 0x24]);
-    let local_name: &str = "Luc's bleyboard";
-    let adv_timeout = Duration::from_secs(120);
+    manufacturer_data.insert(cli.manufacturer_id, vec![0x21, 0x22, 0x23, 0x24]);
+    let local_name: &str = &cli.name;
+    let adv_timeout = cli.adv_timeout();
+    let bonded_host = bonds.host();
+    if let Some(host) = bonded_host {
+        info!(
+            "A host ({}) has bonded before; staying connectable indefinitely so only it reconnects",
+            host
+        );
+    }
     let le_advertisement = Advertisement {
         advertisement_type: Type::Peripheral,
-        service_uuids: vec![SERVICE_BATTERY_UUID, SERVICE_HID_UUID]
-            .into_iter()
-            .collect(),
+        service_uuids: device.service_uuids.into_iter().collect(),
         manufacturer_data,
-        discoverable: Some(true),
+        // Once a host has bonded we no longer need to be generally
+        // discoverable: BlueZ auto-reconnects trusted, paired devices, so
+        // staying connectable is enough. We also drop the advertising
+        // timeout in that case: `bluer`/BlueZ have no knob for directed
+        // advertising at a specific address, so the closest approximation
+        // of "reconnect to the known host" is to keep advertising
+        // connectable until it shows back up, instead of exiting after the
+        // same fixed window used for first-time pairing.
+        discoverable: Some(bonded_host.is_none()),
         // The keyboard appearance seems not to work when SERVICE_UUID is not the standard 0x1812.
-        appearance: Some(APPEARANCE_HID_GAMEPAD),
-        // TODO use a commandline argument.
-        // Maximum is 180 seconds. See §5.1.1.
-        timeout: Some(adv_timeout),
-        // TODO take the name from a command line argument.
+        appearance: device.appearance,
+        timeout: bonded_host.is_none().then_some(adv_timeout),
         local_name: Some(local_name.to_string()),
         ..Default::default()
     };
@@ -123,196 +167,120 @@ async fn main() -> bluer::Result<()> {
         adapter.name()
     );
 
-    print!("Configuring HID Keyboard services\u{2026} ");
-    let (service_control, service_handle) = service_control();
-    let (char_control, char_handle) = characteristic_control();
-    let sbattery: Service = Service {
-        uuid: SERVICE_BATTERY_UUID,
-        primary: true,
-        characteristics: vec![Characteristic {
-            uuid: CHARACTERISTIC_UUID,
-            write: Some(CharacteristicWrite {
-                write: true,
-                write_without_response: true,
-                method: CharacteristicWriteMethod::Io,
-                ..Default::default()
-            }),
-            notify: Some(CharacteristicNotify {
-                notify: true,
-                method: CharacteristicNotifyMethod::Io,
-                ..Default::default()
-            }),
-            control_handle: char_handle,
-            ..Default::default()
-        }],
-        control_handle: service_handle,
-        ..Default::default()
-    };
-    let sdevice_info: Service = Service {
-        uuid: SERVICE_DEVICE_INFO_UUID,
-        primary: true,
-        characteristics: vec![
-            // TODO write the constant values in these characteristics.
-            Characteristic {
-                // Model Number 0x2A24
-                uuid: uuid::Uuid::from_u128(0x00002a2400001000800000805f9b34fb),
-                read: Some(CharacteristicRead {
-                    read: true,
-                    ..Default::default()
-                }),
-                ..Default::default()
-            },
-            Characteristic {
-                // Serial Number 0x2A25
-                uuid: uuid::Uuid::from_u128(0x00002a2500001000800000805f9b34fb),
-                read: Some(CharacteristicRead {
-                    read: true,
-                    ..Default::default()
-                }),
-                ..Default::default()
-            },
-            Characteristic {
-                // Firmware Revision 0x2A26
-                uuid: uuid::Uuid::from_u128(0x00002a2600001000800000805f9b34fb),
-                read: Some(CharacteristicRead {
-                    read: true,
-                    ..Default::default()
-                }),
-                ..Default::default()
-            },
-            Characteristic {
-                // Hardware Revision 0x2a27
-                uuid: uuid::Uuid::from_u128(0x00002a2700001000800000805f9b34fb),
-                read: Some(CharacteristicRead {
-                    read: true,
-                    ..Default::default()
-                }),
-                ..Default::default()
-            },
-            Characteristic {
-                // Software Revision 0x2a28
-                uuid: uuid::Uuid::from_u128(0x00002a2800001000800000805f9b34fb),
-                read: Some(CharacteristicRead {
-                    read: true,
-                    ..Default::default()
-                }),
-                ..Default::default()
-            },
-            Characteristic {
-                // Manufacturer 0x2a29
-                uuid: uuid::Uuid::from_u128(0x00002a2900001000800000805f9b34fb),
-                read: Some(CharacteristicRead {
-                    read: true,
-                    ..Default::default()
-                }),
-                ..Default::default()
-            },
-        ],
-        ..Default::default()
-    };
-    let sscan_params: Service = Service {
-        uuid: SERVICE_SCAN_PARAMS_UUID,
-        primary: false,
-        // TODO set characteristics
-        ..Default::default()
-    };
-    let shid: Service = Service {
-        uuid: SERVICE_HID_UUID,
-        primary: true,
-        // TODO set characteristics
-        ..Default::default()
-    };
-
-    let app = Application {
-        services: vec![sbattery, sdevice_info, sscan_params, shid],
-        ..Default::default()
-    };
-    let app_handle = adapter.serve_gatt_application(app).await?;
-    println!("{}", Green.bold().paint("OK"));
-
-    info!("Service handle is 0x{:x}", service_control.handle()?);
-    info!("Characteristic handle is 0x{:x}", char_control.handle()?);
-
     println!(
         "{}",
-        Green.paint("HID Keyboard is ready and advertising. Press Enter to stop.")
+        Green.paint(
+            "HID Keyboard is ready and advertising. Type a line to send it as keystrokes once a \
+             host subscribes, or press Enter alone to stop."
+        )
     );
     let scanning_progression = ProgressBar::new_spinner();
     scanning_progression.enable_steady_tick(Duration::from_millis(100));
-    scanning_progression.set_message(format!(
-        "{} is advertising for {} second(s)",
-        Style::new().underline().paint(local_name),
-        Style::new()
-            .underline()
-            //            .paint(format!("{}", adv_timeout.as_seconds_f32()))
-            .paint(format!("{}", 120)) // TODO replace with adv_timeout
-    ));
+    scanning_progression.set_message(if bonded_host.is_none() {
+        format!(
+            "{} is advertising for {} second(s)",
+            Style::new().underline().paint(local_name),
+            Style::new()
+                .underline()
+                .paint(format!("{}", adv_timeout.as_secs()))
+        )
+    } else {
+        format!(
+            "{} is advertising indefinitely for its bonded host",
+            Style::new().underline().paint(local_name)
+        )
+    });
 
     let stdin = BufReader::new(tokio::io::stdin());
     let mut lines = stdin.lines();
     let adv_sleep = sleep(adv_timeout);
     tokio::pin!(adv_sleep);
 
-    let mut read_buf = Vec::new();
-    let mut reader_opt: Option<CharacteristicReader> = None;
-    let mut writer_opt: Option<CharacteristicWriter> = None;
-    pin_mut!(char_control);
+    let mut keyboard_opt: Option<hid::Keyboard> = None;
+    let mut battery_opt: Option<battery::Monitor> = None;
+    let mut battery_tick = interval(battery::UPDATE_INTERVAL);
+    let adapter_events = adapter.events().await?;
+    let (bonded_tx, mut bonded_rx) = mpsc::unbounded_channel();
+    pin_mut!(battery_control);
+    pin_mut!(hid_report_control);
+    pin_mut!(adapter_events);
 
     loop {
         tokio::select! {
-            _ = &mut adv_sleep => {
+            // Disabled once a host has bonded: we advertise connectable
+            // indefinitely for it instead of exiting after the same fixed
+            // window used for first-time pairing (see the timeout comment
+            // above).
+            _ = &mut adv_sleep, if bonded_host.is_none() => {
                 scanning_progression.finish_with_message("Advertisement timed out.");
                 break;
             }
-            _ = lines.next_line() => {
-                scanning_progression.finish_and_clear();
-                break;
+            line = lines.next_line() => {
+                match line {
+                    Ok(Some(text)) if !text.is_empty() => {
+                        match keyboard_opt.as_mut() {
+                            Some(keyboard) => {
+                                if let Err(err) = keyboard.type_text(&text).await {
+                                    error!("Failed to send keystrokes: {}", err);
+                                }
+                            }
+                            None => println!(
+                                "No host subscribed to HID Input Report yet; nothing to type."
+                            ),
+                        }
+                    }
+                    _ => {
+                        scanning_progression.finish_and_clear();
+                        break;
+                    }
+                }
+            }
+            evt = adapter_events.next() => {
+                if let Some(bluer::AdapterEvent::DeviceAdded(address)) = evt {
+                    let adapter = adapter.clone();
+                    let bonded_tx = bonded_tx.clone();
+                    tokio::spawn(async move {
+                        if let Err(err) = bonding::watch_for_pairing(&adapter, address, bonded_tx).await {
+                            error!("Failed to watch {} for pairing: {}", address, err);
+                        }
+                    });
+                }
+            }
+            Some(address) = bonded_rx.recv() => {
+                if let Err(err) = bonding::remember(&adapter, address, &mut bonds).await {
+                    error!("Failed to process bonding for {}: {}", address, err);
+                }
             }
-            evt = char_control.next() => {
-                // DEV
-                info!("CharacteristicControl got an event: {:?}", evt);
+            evt = battery_control.next() => {
+                info!("Battery Level control got an event: {:?}", evt);
 
                 match evt {
-                    Some(CharacteristicControlEvent::Write(req)) => {
-                        println!("Accepting write request event with MTU {}", req.mtu());
-                        read_buf = vec![0; req.mtu()];
-                        reader_opt = Some(req.accept()?);
-                    },
                     Some(CharacteristicControlEvent::Notify(notifier)) => {
-                        println!("Accepting notify request event with MTU {}", notifier.mtu());
-                        writer_opt = Some(notifier);
+                        println!("Host subscribed to Battery Level notifications");
+                        battery_opt = Some(battery::Monitor::new(notifier));
                     },
+                    Some(CharacteristicControlEvent::Write(_)) => {},
                     None => break,
                 }
             },
-            read_res = async {
-                match &mut reader_opt {
-                    Some(reader) if writer_opt.is_some() => reader.read(&mut read_buf).await,
-                    _ => future::pending().await,
+            evt = hid_report_control.next() => {
+                info!("HID Input Report control got an event: {:?}", evt);
+
+                match evt {
+                    Some(CharacteristicControlEvent::Notify(notifier)) => {
+                        println!("Host subscribed to HID Input Report notifications");
+                        keyboard_opt = Some(hid::Keyboard::new(notifier, hid_report_state.clone()));
+                    },
+                    Some(CharacteristicControlEvent::Write(_)) => {},
+                    None => break,
                 }
-            } => {
-                // DEV
-                println!("Read trial? {:?}", read_res);
-                match read_res {
-                    Ok(0) => {
-                        println!("Read stream ended");
-                        reader_opt = None;
-                    }
-                    Ok(n) => {
-                        let value = read_buf[..n].to_vec();
-                        println!("Echoing {} bytes: {:x?} ... {:x?}", value.len(), &value[0..4.min(value.len())], &value[value.len().saturating_sub(4) ..]);
-                        if value.len() < 512 {
-                            println!("DEV value.len() < 512: {}", value.len());
-                            println!();
-                        }
-                        if let Err(err) = writer_opt.as_mut().unwrap().write_all(&value).await {
-                            println!("Write failed: {}", &err);
-                            writer_opt = None;
-                        }
-                    }
-                    Err(err) => {
-                        println!("Read stream error: {}", &err);
-                        reader_opt = None;
+            },
+            _ = battery_tick.tick() => {
+                if let Some(monitor) = battery_opt.as_mut() {
+                    let percent = battery::read_percent();
+                    if let Err(err) = monitor.notify(percent).await {
+                        error!("Battery Level notify failed: {}", err);
+                        battery_opt = None;
                     }
                 }
             }